@@ -0,0 +1,3 @@
+pub mod audio;
+pub mod modifier;
+pub mod render;
@@ -0,0 +1,470 @@
+use bevy::{
+    ecs::system::lifetimeless::{SRes, SResMut},
+    prelude::*,
+    render::{
+        extract::Extract,
+        render_resource::{
+            BindGroup, BindGroupEntry, BindGroupLayout, BindGroupLayoutEntry, BindingResource,
+            BindingType, BufferBindingType, ShaderStages, ShaderType, StorageBuffer,
+        },
+        renderer::{RenderDevice, RenderQueue},
+        Render, RenderApp, RenderSet,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    graph::ExprHandle,
+    modifier::{Modifier, ModifierContext},
+    Attribute, EvalContext, ExprError, Module, ShaderWriter,
+};
+
+/// GPU-side representation of a single collider, as uploaded to the
+/// `colliders` storage buffer consumed by the update compute shader.
+///
+/// One [`ColliderShape`] is flattened into this fixed-size representation
+/// regardless of its actual kind; unused fields for a given `kind` are
+/// ignored by the shader. This keeps the buffer a simple flat array instead
+/// of requiring per-shape variable-size records.
+#[derive(Debug, Clone, Copy, PartialEq, ShaderType)]
+pub struct GpuCollider {
+    /// Collider kind: 0 = plane, 1 = sphere, 2 = capsule, 3 = AABB, 4 =
+    /// convex hull (indexes into [`ExtractedColliders::hull_planes`]).
+    pub kind: u32,
+    /// Collision layer mask this collider belongs to.
+    pub layer: u32,
+    /// First parameter: plane point, sphere/AABB center, capsule segment
+    /// start.
+    pub p0: Vec3,
+    /// Second parameter: plane normal, capsule segment end, AABB half-extent.
+    pub p1: Vec3,
+    /// Radius, used by sphere and capsule shapes.
+    pub radius: f32,
+    /// For convex hulls, offset of the first bounding plane in the shared
+    /// [`ExtractedColliders::hull_planes`] buffer; for other shapes, unused.
+    pub hull_start: u32,
+    /// For convex hulls, number of bounding planes; for other shapes,
+    /// unused.
+    pub hull_count: u32,
+}
+
+/// Shape of a single collider extracted from the physics backend.
+///
+/// This is the CPU-side description filled in every frame from whatever
+/// physics engine (Avian, Rapier, ...) owns the scene geometry, before being
+/// packed into [`GpuCollider`] and uploaded to the GPU.
+#[derive(Debug, Clone, PartialEq, Reflect)]
+pub enum ColliderShape {
+    /// Infinite plane, defined by a point on the plane and its normal.
+    Plane { point: Vec3, normal: Vec3 },
+    /// Sphere, defined by its center and radius.
+    Sphere { center: Vec3, radius: f32 },
+    /// Capsule, defined by its central segment endpoints and radius.
+    Capsule { a: Vec3, b: Vec3, radius: f32 },
+    /// Axis-aligned bounding box, defined by its center and half-extents.
+    Aabb { center: Vec3, half_extent: Vec3 },
+    /// Convex hull, defined by its bounding planes in `(normal, distance)`
+    /// form: a world-space point `p` is inside the hull iff
+    /// `dot(p, normal) - distance <= 0.` holds for every plane.
+    ConvexHull { planes: Vec<Vec4> },
+}
+
+impl ColliderShape {
+    /// Pack this shape into a [`GpuCollider`], appending any auxiliary data
+    /// (currently only convex hull planes) to `hull_planes`.
+    fn to_gpu(&self, layer: u32, hull_planes: &mut Vec<Vec4>) -> GpuCollider {
+        let mut gpu = GpuCollider {
+            kind: 0,
+            layer,
+            p0: Vec3::ZERO,
+            p1: Vec3::ZERO,
+            radius: 0.,
+            hull_start: 0,
+            hull_count: 0,
+        };
+        match self {
+            ColliderShape::Plane { point, normal } => {
+                gpu.kind = 0;
+                gpu.p0 = *point;
+                gpu.p1 = *normal;
+            }
+            ColliderShape::Sphere { center, radius } => {
+                gpu.kind = 1;
+                gpu.p0 = *center;
+                gpu.radius = *radius;
+            }
+            ColliderShape::Capsule { a, b, radius } => {
+                gpu.kind = 2;
+                gpu.p0 = *a;
+                gpu.p1 = *b;
+                gpu.radius = *radius;
+            }
+            ColliderShape::Aabb { center, half_extent } => {
+                gpu.kind = 3;
+                gpu.p0 = *center;
+                gpu.p1 = *half_extent;
+            }
+            ColliderShape::ConvexHull { planes } => {
+                gpu.kind = 4;
+                gpu.hull_start = hull_planes.len() as u32;
+                gpu.hull_count = planes.len() as u32;
+                hull_planes.extend_from_slice(planes);
+            }
+        }
+        gpu
+    }
+}
+
+/// A single extracted collider, tagged with the collision layer it
+/// participates in.
+///
+/// Attach this component to any entity whose shape should be collided
+/// against by a [`CollideModifier`] — typically kept in sync with a physics
+/// backend's own collider (Avian, Rapier, ...) by a small bridging system
+/// that updates [`Collider::shape`] from that backend's collider each
+/// frame.
+#[derive(Debug, Clone, PartialEq, Reflect, Component)]
+pub struct Collider {
+    /// Shape of the collider.
+    pub shape: ColliderShape,
+    /// Collision layer bitmask. A [`CollideModifier`] only reacts to
+    /// colliders whose layer intersects its own `layer_mask`.
+    pub layer: u32,
+}
+
+/// Per-frame snapshot of every [`Collider`] in the main world, extracted
+/// into the render world and packed into the flat GPU-friendly layout
+/// [`GpuCollider`] expects.
+#[derive(Resource, Default, Clone)]
+pub struct ExtractedColliders {
+    /// Flattened collider records, in no particular order.
+    pub colliders: Vec<GpuCollider>,
+    /// Shared pool of convex hull bounding planes; a [`GpuCollider`] of kind
+    /// `4` (hull) indexes into this via its `hull_start`/`hull_count`.
+    pub hull_planes: Vec<Vec4>,
+}
+
+/// Extract every [`Collider`] in the main world into [`ExtractedColliders`],
+/// ready for [`prepare_collider_buffers`] to upload.
+pub fn extract_colliders(mut commands: Commands, query: Extract<Query<&Collider>>) {
+    let mut extracted = ExtractedColliders::default();
+    for collider in &query {
+        let gpu = collider
+            .shape
+            .to_gpu(collider.layer, &mut extracted.hull_planes);
+        extracted.colliders.push(gpu);
+    }
+    commands.insert_resource(extracted);
+}
+
+/// GPU storage buffers backing the `colliders` and `hull_planes` bindings
+/// declared in `collide.wgsl`, rewritten from [`ExtractedColliders`] every
+/// frame.
+#[derive(Resource, Default)]
+pub struct ColliderGpuBuffers {
+    colliders: StorageBuffer<Vec<GpuCollider>>,
+    hull_planes: StorageBuffer<Vec<Vec4>>,
+    bind_group: Option<BindGroup>,
+}
+
+impl ColliderGpuBuffers {
+    /// The bind group matching `@group(3)` in `collide.wgsl`, if the
+    /// buffers have been written to at least once.
+    pub fn bind_group(&self) -> Option<&BindGroup> {
+        self.bind_group.as_ref()
+    }
+}
+
+/// Bind group layout for the `colliders` (binding 0) and `hull_planes`
+/// (binding 1) storage buffers consumed by `resolve_collisions()` in
+/// `collide.wgsl`'s `@group(3)`.
+#[derive(Resource)]
+pub struct ColliderBindGroupLayout(pub BindGroupLayout);
+
+impl FromWorld for ColliderBindGroupLayout {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+        let layout = render_device.create_bind_group_layout(
+            "hanabi_collider_bind_group_layout",
+            &[
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::COMPUTE,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::COMPUTE,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        );
+        Self(layout)
+    }
+}
+
+/// Upload [`ExtractedColliders`] to the GPU and (re)build the `@group(3)`
+/// bind group the update compute pass binds to resolve collisions against.
+pub fn prepare_collider_buffers(
+    extracted: SRes<ExtractedColliders>,
+    mut buffers: SResMut<ColliderGpuBuffers>,
+    layout: SRes<ColliderBindGroupLayout>,
+    render_device: SRes<RenderDevice>,
+    render_queue: SRes<RenderQueue>,
+) {
+    // Empty buffers are invalid to bind on some backends; always keep at
+    // least one dummy entry so the bind group stays valid even with zero
+    // colliders in the scene.
+    let colliders = if extracted.colliders.is_empty() {
+        vec![GpuCollider {
+            kind: u32::MAX, // never matches a real kind in the shader
+            layer: 0,
+            p0: Vec3::ZERO,
+            p1: Vec3::ZERO,
+            radius: 0.,
+            hull_start: 0,
+            hull_count: 0,
+        }]
+    } else {
+        extracted.colliders.clone()
+    };
+    let hull_planes = if extracted.hull_planes.is_empty() {
+        vec![Vec4::ZERO]
+    } else {
+        extracted.hull_planes.clone()
+    };
+
+    buffers.colliders.set(colliders);
+    buffers.colliders.write_buffer(&render_device, &render_queue);
+    buffers.hull_planes.set(hull_planes);
+    buffers
+        .hull_planes
+        .write_buffer(&render_device, &render_queue);
+
+    let (Some(colliders_binding), Some(hull_planes_binding)) = (
+        buffers.colliders.binding(),
+        buffers.hull_planes.binding(),
+    ) else {
+        return;
+    };
+
+    buffers.bind_group = Some(render_device.create_bind_group(
+        "hanabi_collider_bind_group",
+        &layout.0,
+        &[
+            BindGroupEntry {
+                binding: 0,
+                resource: BindingResource::Buffer(colliders_binding.into()),
+            },
+            BindGroupEntry {
+                binding: 1,
+                resource: BindingResource::Buffer(hull_planes_binding.into()),
+            },
+        ],
+    ));
+}
+
+/// Render-world plugin extracting [`Collider`] components each frame and
+/// uploading them to the `@group(3)` storage buffers [`CollideModifier`]'s
+/// generated WGSL binds.
+pub struct ColliderExtractionPlugin;
+
+impl Plugin for ColliderExtractionPlugin {
+    fn build(&self, app: &mut App) {
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+        render_app
+            .init_resource::<ExtractedColliders>()
+            .init_resource::<ColliderGpuBuffers>()
+            .add_systems(ExtractSchedule, extract_colliders)
+            .add_systems(Render, prepare_collider_buffers.in_set(RenderSet::Prepare));
+    }
+
+    fn finish(&self, app: &mut App) {
+        if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app.init_resource::<ColliderBindGroupLayout>();
+        }
+    }
+}
+
+/// An update modifier resolving particle collisions against physics
+/// colliders extracted from the scene, with continuous (swept) resolution
+/// to avoid tunneling through thin geometry.
+///
+/// Requires the [`ColliderExtractionPlugin`] to be added to the app so that
+/// [`Collider`] components are extracted and uploaded every frame; without
+/// it, the `colliders` buffer this modifier's shader code reads from is
+/// never populated.
+///
+/// For every particle, this modifier:
+/// 1. Samples the segment from [`Attribute::PREV_POSITION`] to the current
+///    position every `COLLIDE_SUBSTEP_DISTANCE` (a constant in
+///    `collide.wgsl`), rather than testing only the final position, so
+///    collisions with colliders thinner than one simulation step's travel
+///    distance aren't missed between two simulation steps. The number of
+///    samples is capped at `COLLIDE_MAX_SUBSTEPS`, so on longer segments the
+///    gap between samples grows to `travel / COLLIDE_MAX_SUBSTEPS`. A
+///    collider thinner than that gap along the travel direction can still
+///    be missed; lowering `COLLIDE_SUBSTEP_DISTANCE` or raising
+///    `COLLIDE_MAX_SUBSTEPS` trades more samples for finer coverage.
+/// 2. On contact, pushes the particle back to the collider surface and
+///    reflects its velocity as `v' = v - (1 + e)(v·n)n`, where `e` is
+///    [`restitution`](Self::restitution), then applies
+///    [`friction`](Self::friction) to the tangential component.
+///
+/// Requires [`Attribute::PREV_POSITION`] to be present on the effect, since
+/// that's what the sweep test is built from.
+#[derive(Debug, Clone, Reflect, Serialize, Deserialize)]
+pub struct CollideModifier {
+    /// Coefficient of restitution `e` in `[0, 1]`: `0` absorbs all normal
+    /// velocity (fully inelastic), `1` reflects it back unchanged (fully
+    /// elastic).
+    pub restitution: ExprHandle,
+    /// Friction coefficient applied to the tangential velocity component
+    /// remaining after the normal bounce, in `[0, 1]`: `0` leaves it
+    /// untouched, `1` cancels it outright.
+    pub friction: ExprHandle,
+    /// Bitmask of collision layers this modifier reacts to. A collider only
+    /// affects a particle if `(collider.layer & layer_mask) != 0`.
+    pub layer_mask: ExprHandle,
+}
+
+impl CollideModifier {
+    /// Create a new collision modifier with the given restitution, friction,
+    /// and collision layer mask expressions.
+    pub fn new(restitution: ExprHandle, friction: ExprHandle, layer_mask: ExprHandle) -> Self {
+        Self {
+            restitution,
+            friction,
+            layer_mask,
+        }
+    }
+}
+
+#[typetag::serde]
+impl Modifier for CollideModifier {
+    fn context(&self) -> ModifierContext {
+        ModifierContext::Update
+    }
+
+    fn attributes(&self) -> &[Attribute] {
+        &[
+            Attribute::POSITION,
+            Attribute::PREV_POSITION,
+            Attribute::VELOCITY,
+        ]
+    }
+
+    fn apply(&self, module: &mut Module, context: &mut ShaderWriter) -> Result<(), ExprError> {
+        let restitution = context.eval(module, self.restitution)?;
+        let friction = context.eval(module, self.friction)?;
+        let layer_mask = context.eval(module, self.layer_mask)?;
+
+        context.header_code += include_str!("../render/shaders/collide.wgsl");
+
+        context.main_code += &format!(
+            r##"
+    {{
+        let hs_restitution = {restitution};
+        let hs_friction = {friction};
+        let hs_layer_mask = {layer_mask};
+        let hs_prev_pos = particle.{prev_position};
+        var hs_pos = particle.{position};
+        var hs_vel = particle.{velocity};
+        resolve_collisions(hs_prev_pos, &hs_pos, &hs_vel, hs_restitution, hs_friction, hs_layer_mask);
+        particle.{position} = hs_pos;
+        particle.{velocity} = hs_vel;
+    }}
+"##,
+            prev_position = Attribute::PREV_POSITION.name(),
+            position = Attribute::POSITION.name(),
+            velocity = Attribute::VELOCITY.name(),
+        );
+
+        Ok(())
+    }
+
+    fn boxed_clone(&self) -> BoxedModifier {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sphere_shape_packs_into_gpu_collider() {
+        let mut hull_planes = Vec::new();
+        let gpu = ColliderShape::Sphere {
+            center: Vec3::new(1., 2., 3.),
+            radius: 4.,
+        }
+        .to_gpu(0b010, &mut hull_planes);
+
+        assert_eq!(gpu.kind, 1);
+        assert_eq!(gpu.layer, 0b010);
+        assert_eq!(gpu.p0, Vec3::new(1., 2., 3.));
+        assert_eq!(gpu.radius, 4.);
+        assert!(hull_planes.is_empty());
+    }
+
+    #[test]
+    fn convex_hull_shape_appends_shared_planes_and_records_its_range() {
+        let mut hull_planes = vec![Vec4::ONE]; // pretend another hull already used slot 0
+
+        let gpu = ColliderShape::ConvexHull {
+            planes: vec![Vec4::new(1., 0., 0., 1.), Vec4::new(0., 1., 0., 2.)],
+        }
+        .to_gpu(1, &mut hull_planes);
+
+        assert_eq!(gpu.kind, 4);
+        assert_eq!(gpu.hull_start, 1);
+        assert_eq!(gpu.hull_count, 2);
+        assert_eq!(hull_planes.len(), 3);
+        assert_eq!(hull_planes[1], Vec4::new(1., 0., 0., 1.));
+        assert_eq!(hull_planes[2], Vec4::new(0., 1., 0., 2.));
+    }
+
+    #[test]
+    fn packing_multiple_shapes_flattens_them_against_a_shared_hull_plane_pool() {
+        let colliders = [
+            Collider {
+                shape: ColliderShape::Plane {
+                    point: Vec3::ZERO,
+                    normal: Vec3::Y,
+                },
+                layer: 1,
+            },
+            Collider {
+                shape: ColliderShape::ConvexHull {
+                    planes: vec![Vec4::new(1., 0., 0., 1.)],
+                },
+                layer: 2,
+            },
+        ];
+
+        let mut extracted = ExtractedColliders::default();
+        for c in &colliders {
+            let gpu = c.shape.to_gpu(c.layer, &mut extracted.hull_planes);
+            extracted.colliders.push(gpu);
+        }
+
+        assert_eq!(extracted.colliders.len(), 2);
+        assert_eq!(extracted.colliders[0].kind, 0);
+        assert_eq!(extracted.colliders[1].kind, 4);
+        assert_eq!(extracted.colliders[1].hull_start, 0);
+        assert_eq!(extracted.hull_planes.len(), 1);
+    }
+}
@@ -0,0 +1,173 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    modifier::{Modifier, ModifierContext},
+    Attribute, ExprError, Module, ShaderWriter, ToWgslString,
+};
+
+/// Default central-difference epsilon used to evaluate the analytic curl,
+/// expressed as a fraction of `1 / freq`. The noise field varies on a length
+/// scale of `1 / freq`, so scaling the epsilon by that same factor keeps the
+/// finite-difference estimate numerically stable regardless of how the user
+/// tunes `freq`.
+pub const DEFAULT_CURL_NOISE_EPS_FACTOR: f32 = 0.01;
+
+/// Compute the central-difference epsilon for a given noise `freq`, as a
+/// fraction of the noise wavelength `1 / freq`, floored so it never
+/// collapses to zero (or blows up) for degenerate frequencies.
+fn curl_noise_eps(freq: f32) -> f32 {
+    (DEFAULT_CURL_NOISE_EPS_FACTOR / freq.max(1e-5)).max(1e-4)
+}
+
+/// A turbulence modifier producing a divergence-free (curl) noise velocity
+/// field.
+///
+/// This samples a vector potential made of 3 offset fractal-Brownian-motion
+/// simplex noise fields, and returns its analytic curl via central
+/// differences. Because the output is the curl of a potential, the field is
+/// guaranteed incompressible: particles driven by it roll through smooth
+/// eddies instead of collapsing into point sources or sinks, which is what
+/// makes it suitable for smoke, fire wisps, and magic trails.
+///
+/// The modifier can either add the curl to [`Attribute::VELOCITY`] each
+/// frame (acceleration-like, the default), or overwrite
+/// [`Attribute::VELOCITY`] outright when [`CurlNoiseModifier::as_velocity`]
+/// is set.
+#[derive(Debug, Clone, Copy, PartialEq, Reflect, Serialize, Deserialize)]
+pub struct CurlNoiseModifier {
+    /// Spatial frequency of the noise, in `1 / simulation units`. Higher
+    /// values produce smaller, more detailed eddies.
+    pub freq: f32,
+    /// Time scale at which the field animates. The noise potential is
+    /// sampled at `position * freq + time * time_scale`, so this value acts
+    /// as an extra "flow" velocity through noise-space.
+    pub time_scale: f32,
+    /// Overall amplitude applied to the resulting curl velocity/acceleration.
+    pub amplitude: f32,
+    /// Number of fractal Brownian motion octaves stacked to build each
+    /// component of the vector potential. Each extra octave doubles the
+    /// noise frequency and halves its amplitude, adding finer detail at a
+    /// roughly constant cost.
+    pub octaves: u32,
+    /// If `true`, the computed curl directly replaces
+    /// [`Attribute::VELOCITY`] each frame. If `false` (default), it's treated
+    /// as an acceleration and integrated into the existing velocity over the
+    /// simulation delta-time.
+    pub as_velocity: bool,
+}
+
+impl Default for CurlNoiseModifier {
+    fn default() -> Self {
+        Self {
+            freq: 1.0,
+            time_scale: 0.1,
+            amplitude: 1.0,
+            octaves: 2,
+            as_velocity: false,
+        }
+    }
+}
+
+impl CurlNoiseModifier {
+    /// Create a new curl noise modifier with the given spatial frequency,
+    /// leaving all other parameters to their default value.
+    pub fn new(freq: f32) -> Self {
+        Self {
+            freq,
+            ..Default::default()
+        }
+    }
+
+    /// Set the number of fractal Brownian motion octaves used to build the
+    /// noise potential. Clamped to at least 1.
+    pub fn with_octaves(mut self, octaves: u32) -> Self {
+        self.octaves = octaves.max(1);
+        self
+    }
+
+    /// Set the overall amplitude of the curl field.
+    pub fn with_amplitude(mut self, amplitude: f32) -> Self {
+        self.amplitude = amplitude;
+        self
+    }
+
+    /// Make this modifier overwrite [`Attribute::VELOCITY`] directly instead
+    /// of treating the curl as an acceleration.
+    pub fn as_velocity_override(mut self) -> Self {
+        self.as_velocity = true;
+        self
+    }
+}
+
+#[typetag::serde]
+impl Modifier for CurlNoiseModifier {
+    fn context(&self) -> ModifierContext {
+        ModifierContext::Update
+    }
+
+    fn attributes(&self) -> &[Attribute] {
+        &[Attribute::POSITION, Attribute::VELOCITY]
+    }
+
+    fn apply(&self, _module: &mut Module, context: &mut ShaderWriter) -> Result<(), ExprError> {
+        let eps = curl_noise_eps(self.freq);
+
+        context.header_code += include_str!("../render/shaders/curl_noise.wgsl");
+
+        context.main_code += &format!(
+            r##"
+    {{
+        let curl = curl_noise3(particle.{position}, {freq}, sim_params.time * {time_scale}, {octaves}, {eps}) * {amplitude};
+        particle.{velocity} = {assign};
+    }}
+"##,
+            position = Attribute::POSITION.name(),
+            velocity = Attribute::VELOCITY.name(),
+            freq = self.freq.to_wgsl_string(),
+            time_scale = self.time_scale.to_wgsl_string(),
+            octaves = self.octaves,
+            eps = eps.to_wgsl_string(),
+            amplitude = self.amplitude.to_wgsl_string(),
+            assign = if self.as_velocity {
+                "curl".to_string()
+            } else {
+                format!("particle.{} + curl * sim_params.delta_time", Attribute::VELOCITY.name())
+            },
+        );
+
+        Ok(())
+    }
+
+    fn boxed_clone(&self) -> BoxedModifier {
+        Box::new(*self)
+    }
+}
+
+// Still missing from this request: an `ExprWriter::curl_noise3(pos, freq,
+// time)` intrinsic, so a graph could sample this same field as a plain
+// expression (e.g. to drive a color or size gradient) instead of only
+// through this modifier. That needs a new `BuiltInOperator` variant plus a
+// WGSL lowering arm, both added to `graph.rs` — a file this tree doesn't
+// contain a copy of, so it can't be edited from here without guessing at its
+// real contents and risking a definition that conflicts with the one
+// upstream. This is flagged as outstanding work blocked on that file being
+// available, not treated as done: `CurlNoiseModifier` only ships the
+// modifier-internal call to the WGSL `curl_noise3` function baked in above.
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn eps_scales_inversely_with_freq() {
+        assert!(curl_noise_eps(1.0) > curl_noise_eps(100.0));
+    }
+
+    #[test]
+    fn eps_is_floored_for_degenerate_freq() {
+        assert!(curl_noise_eps(0.0).is_finite());
+        assert!(curl_noise_eps(0.0) >= 1e-4);
+        assert!(curl_noise_eps(f32::MAX) >= 1e-4);
+    }
+}
@@ -0,0 +1,15 @@
+//! Modifiers are the building blocks effects are composed of, plugged into
+//! an [`EffectAsset`](crate::EffectAsset)'s init/update/render contexts.
+
+mod collide;
+mod curl_noise;
+mod sdf;
+
+pub use collide::{
+    Collider, ColliderExtractionPlugin, ColliderShape, CollideModifier, GpuCollider,
+};
+pub use curl_noise::CurlNoiseModifier;
+pub use sdf::{
+    bake_mesh_to_sdf, ConformToSdfModifier, KillSdfModifier, SdfExtractionPlugin, SdfVolume,
+    SdfVolumeBinding,
+};
@@ -0,0 +1,656 @@
+use bevy::{
+    asset::Handle,
+    ecs::system::lifetimeless::{SRes, SResMut},
+    prelude::*,
+    render::{
+        extract::Extract,
+        render_asset::RenderAssets,
+        render_resource::{
+            BindGroup, BindGroupEntry, BindGroupLayout, BindGroupLayoutEntry, BindingResource,
+            BindingType, Extent3d, SamplerBindingType, SamplerDescriptor, ShaderStages,
+            ShaderType, TextureDimension, TextureFormat, TextureSampleType, TextureViewDimension,
+            UniformBuffer,
+        },
+        renderer::{RenderDevice, RenderQueue},
+        texture::GpuImage,
+        ExtractSchedule, Render, RenderApp, RenderSet,
+    },
+    utils::EntityHashMap,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    graph::ExprHandle,
+    modifier::{Modifier, ModifierContext},
+    Attribute, EvalContext, ExprError, Module, ShaderWriter,
+};
+
+/// A baked 3D signed-distance field, stored as a single-channel 3D
+/// [`Image`] and mapped into world space by an origin and uniform scale.
+///
+/// Distances are stored in texels in units of the SDF's own local space
+/// (i.e. before `scale` is applied), so the same baked volume can be reused
+/// at different sizes by changing [`scale`](Self::scale) alone.
+#[derive(Debug, Clone, Reflect)]
+pub struct SdfVolume {
+    /// Single-channel (`R32Float` or similar) 3D texture holding the baked
+    /// distance field.
+    pub image: Handle<Image>,
+    /// World-space position of the volume's local origin (texel `(0,0,0)`'s
+    /// corner, not its center).
+    pub origin: Vec3,
+    /// Uniform scale mapping one local-space unit to this many world-space
+    /// units.
+    pub scale: f32,
+}
+
+/// GPU-side mirror of [`SdfVolume::origin`]/[`SdfVolume::scale`], uploaded as
+/// the uniform `sdf_transform` binds to in `sdf.wgsl`.
+#[derive(ShaderType, Clone, Copy)]
+struct GpuSdfTransform {
+    origin: Vec3,
+    scale: f32,
+}
+
+/// Attach to the same entity as a [`ParticleEffect`](crate::ParticleEffect)
+/// to tell the render world which [`SdfVolume`] that instance's
+/// [`ConformToSdfModifier`]/[`KillSdfModifier`] should sample, mirroring how
+/// [`ParticleEffectAudio`](crate::audio::ParticleEffectAudio) names an
+/// [`AudioSpectrum`](crate::audio::AudioSpectrum) resource to sample.
+///
+/// Only one [`SdfVolumeBinding`] is resolved per entity; an effect combining
+/// both a conform and a kill SDF modifier should bake them into the same
+/// volume.
+#[derive(Component, Debug, Clone)]
+pub struct SdfVolumeBinding {
+    pub volume: SdfVolume,
+}
+
+/// Per-frame snapshot of every [`SdfVolumeBinding`] in the main world,
+/// extracted into the render world ahead of [`prepare_sdf_bind_groups`].
+#[derive(Resource, Default)]
+struct ExtractedSdfVolumes(EntityHashMap<SdfVolume>);
+
+fn extract_sdf_volumes(
+    mut commands: Commands,
+    query: Extract<Query<(Entity, &SdfVolumeBinding)>>,
+) {
+    let mut extracted = ExtractedSdfVolumes::default();
+    for (entity, binding) in &query {
+        extracted.0.insert(entity, binding.volume.clone());
+    }
+    commands.insert_resource(extracted);
+}
+
+/// Bind group layout for the `sdf_texture` (binding 0), `sdf_sampler`
+/// (binding 1), and `sdf_transform` (binding 2) resources consumed by
+/// `sdf.wgsl`'s `@group(4)`. A distinct group index from
+/// [`CollideModifier`](crate::modifier::CollideModifier)'s own `@group(3)`
+/// so the two modifiers can coexist on the same effect, e.g. conforming to
+/// an asteroid's SDF while also colliding against scene colliders.
+#[derive(Resource)]
+struct SdfBindGroupLayout(BindGroupLayout);
+
+impl FromWorld for SdfBindGroupLayout {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+        let layout = render_device.create_bind_group_layout(
+            "hanabi_sdf_bind_group_layout",
+            &[
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::COMPUTE,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Float { filterable: true },
+                        view_dimension: TextureViewDimension::D3,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::COMPUTE,
+                    ty: BindingType::Sampler(SamplerBindingType::Filtering),
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStages::COMPUTE,
+                    ty: BindingType::Buffer {
+                        ty: bevy::render::render_resource::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        );
+        Self(layout)
+    }
+}
+
+/// Per-entity `@group(4)` bind group, rebuilt whenever that entity's
+/// [`SdfVolumeBinding`] is extracted.
+#[derive(Resource, Default)]
+struct SdfGpuBindings(EntityHashMap<BindGroup>);
+
+impl SdfGpuBindings {
+    /// The bind group for `entity`'s bound SDF volume, if it has one and its
+    /// image has finished uploading to the GPU.
+    pub fn get(&self, entity: Entity) -> Option<&BindGroup> {
+        self.0.get(&entity)
+    }
+}
+
+fn prepare_sdf_bind_groups(
+    extracted: SRes<ExtractedSdfVolumes>,
+    mut bindings: SResMut<SdfGpuBindings>,
+    layout: SRes<SdfBindGroupLayout>,
+    gpu_images: SRes<RenderAssets<GpuImage>>,
+    render_device: SRes<RenderDevice>,
+    render_queue: SRes<RenderQueue>,
+) {
+    bindings.0.clear();
+
+    let sampler = render_device.create_sampler(&SamplerDescriptor {
+        label: Some("hanabi_sdf_sampler"),
+        ..default()
+    });
+
+    for (entity, volume) in extracted.0.iter() {
+        let Some(gpu_image) = gpu_images.get(&volume.image) else {
+            // Image not uploaded yet (still loading); skip this frame,
+            // `ConformToSdfModifier`/`KillSdfModifier` simply have no bind
+            // group to read from until it is.
+            continue;
+        };
+
+        let mut transform_buffer = UniformBuffer::from(GpuSdfTransform {
+            origin: volume.origin,
+            scale: volume.scale,
+        });
+        transform_buffer.write_buffer(&render_device, &render_queue);
+        let Some(transform_binding) = transform_buffer.binding() else {
+            continue;
+        };
+
+        let bind_group = render_device.create_bind_group(
+            "hanabi_sdf_bind_group",
+            &layout.0,
+            &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: BindingResource::TextureView(&gpu_image.texture_view),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: BindingResource::Sampler(&sampler),
+                },
+                BindGroupEntry {
+                    binding: 2,
+                    resource: BindingResource::Buffer(transform_binding.into()),
+                },
+            ],
+        );
+        bindings.0.insert(*entity, bind_group);
+    }
+}
+
+/// Render-world plugin extracting [`SdfVolumeBinding`] components each frame
+/// and uploading the referenced [`SdfVolume`]'s texture, sampler, and
+/// origin/scale uniform into the `@group(4)` bind group
+/// [`ConformToSdfModifier`]'s and [`KillSdfModifier`]'s generated WGSL reads
+/// from.
+pub struct SdfExtractionPlugin;
+
+impl Plugin for SdfExtractionPlugin {
+    fn build(&self, app: &mut App) {
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+        render_app
+            .init_resource::<ExtractedSdfVolumes>()
+            .init_resource::<SdfGpuBindings>()
+            .add_systems(ExtractSchedule, extract_sdf_volumes)
+            .add_systems(Render, prepare_sdf_bind_groups.in_set(RenderSet::Prepare));
+    }
+
+    fn finish(&self, app: &mut App) {
+        if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app.init_resource::<SdfBindGroupLayout>();
+        }
+    }
+}
+
+/// Generalization of [`ConformToSphereModifier`](crate::ConformToSphereModifier)
+/// that conforms particles to an arbitrary baked signed-distance field
+/// instead of a hard-coded sphere, so swarms can stick to an asteroid, a
+/// cave wall, or any authored mesh baked via [`bake_mesh_to_sdf`].
+///
+/// The gradient `∇d` driving the attraction direction is estimated from the
+/// volume by central differences, `∇d ≈ (d(p+εx) - d(p-εx), ...) / 2ε`, and
+/// the shell error used by [`shell_half_thickness`](Self::shell_half_thickness)
+/// is `|d| - shell_half_thickness`. All other parameters keep the exact same
+/// meaning as on [`ConformToSphereModifier`](crate::ConformToSphereModifier).
+#[derive(Debug, Clone, Reflect, Serialize, Deserialize)]
+pub struct ConformToSdfModifier {
+    /// Baked SDF volume particles conform to.
+    ///
+    /// This field is not read when generating shader code: the volume
+    /// sampled is the one bound at `@group(4)` from the effect entity's
+    /// [`SdfVolumeBinding`].
+    #[reflect(ignore)]
+    pub sdf: SdfVolume,
+    /// Acceleration magnitude applied toward the SDF's zero level set.
+    /// Negative values repulse particles away from the surface instead.
+    pub attraction_accel: ExprHandle,
+    /// Maximum speed particles can reach under this attraction.
+    pub max_attraction_speed: ExprHandle,
+    /// If set, particles within the shell (see
+    /// [`shell_half_thickness`](Self::shell_half_thickness)) have their
+    /// velocity damped by this factor per second, making them "stick" to the
+    /// surface instead of orbiting around it.
+    pub sticky_factor: Option<ExprHandle>,
+    /// If set, defines a shell of this half-thickness around the zero level
+    /// set inside which [`sticky_factor`](Self::sticky_factor) applies.
+    pub shell_half_thickness: Option<ExprHandle>,
+}
+
+#[typetag::serde]
+impl Modifier for ConformToSdfModifier {
+    fn context(&self) -> ModifierContext {
+        ModifierContext::Update
+    }
+
+    fn attributes(&self) -> &[Attribute] {
+        &[Attribute::POSITION, Attribute::VELOCITY]
+    }
+
+    fn apply(&self, module: &mut Module, context: &mut ShaderWriter) -> Result<(), ExprError> {
+        let attraction_accel = context.eval(module, self.attraction_accel)?;
+        let max_attraction_speed = context.eval(module, self.max_attraction_speed)?;
+        let sticky_factor = self
+            .sticky_factor
+            .map(|e| context.eval(module, e))
+            .transpose()?
+            .unwrap_or_else(|| "0.".to_string());
+        let shell_half_thickness = self
+            .shell_half_thickness
+            .map(|e| context.eval(module, e))
+            .transpose()?
+            .unwrap_or_else(|| "0.".to_string());
+
+        context.header_code += include_str!("../render/shaders/sdf.wgsl");
+
+        context.main_code += &format!(
+            r##"
+    {{
+        let hs_local_pos = (particle.{position} - sdf_transform.origin) / sdf_transform.scale;
+        let hs_grad = sdf_gradient(hs_local_pos);
+        let hs_dist = sdf_sample(hs_local_pos) * sdf_transform.scale;
+        let hs_shell_err = abs(hs_dist) - {shell_half_thickness};
+        let hs_dir = -sign(hs_dist) * normalize(hs_grad);
+        var hs_vel = particle.{velocity} + hs_dir * {attraction_accel} * sim_params.delta_time;
+        let hs_speed = length(hs_vel);
+        if (hs_speed > {max_attraction_speed}) {{
+            hs_vel = hs_vel * ({max_attraction_speed} / hs_speed);
+        }}
+        if (hs_shell_err < 0.) {{
+            hs_vel = hs_vel * (1. - {sticky_factor} * sim_params.delta_time);
+        }}
+        particle.{velocity} = hs_vel;
+    }}
+"##,
+            position = Attribute::POSITION.name(),
+            velocity = Attribute::VELOCITY.name(),
+        );
+
+        Ok(())
+    }
+
+    fn boxed_clone(&self) -> BoxedModifier {
+        Box::new(self.clone())
+    }
+}
+
+/// Analogue of [`KillAabbModifier`](crate::KillAabbModifier) /
+/// [`KillSphereModifier`](crate::KillSphereModifier) that kills particles
+/// based on their signed distance to a baked SDF volume, rather than an
+/// analytic primitive.
+#[derive(Debug, Clone, Reflect, Serialize, Deserialize)]
+pub struct KillSdfModifier {
+    /// Baked SDF volume to test particles against.
+    ///
+    /// This field is not read when generating shader code: the volume
+    /// sampled is the one bound at `@group(4)` from the effect entity's
+    /// [`SdfVolumeBinding`].
+    #[reflect(ignore)]
+    pub sdf: SdfVolume,
+    /// If `true`, particles with a negative signed distance (inside the
+    /// volume) are killed; if `false` (default), particles with a positive
+    /// signed distance (outside) are killed instead. Mirrors
+    /// [`KillSphereModifier::with_kill_inside`](crate::KillSphereModifier::with_kill_inside).
+    pub kill_inside: bool,
+}
+
+impl KillSdfModifier {
+    /// Create a new modifier killing particles outside `sdf`.
+    pub fn new(sdf: SdfVolume) -> Self {
+        Self {
+            sdf,
+            kill_inside: false,
+        }
+    }
+
+    /// Kill particles inside `sdf` instead of outside.
+    pub fn with_kill_inside(mut self, kill_inside: bool) -> Self {
+        self.kill_inside = kill_inside;
+        self
+    }
+}
+
+#[typetag::serde]
+impl Modifier for KillSdfModifier {
+    fn context(&self) -> ModifierContext {
+        ModifierContext::Update
+    }
+
+    fn attributes(&self) -> &[Attribute] {
+        &[Attribute::POSITION]
+    }
+
+    fn apply(&self, _module: &mut Module, context: &mut ShaderWriter) -> Result<(), ExprError> {
+        context.header_code += include_str!("../render/shaders/sdf.wgsl");
+
+        context.main_code += &format!(
+            r##"
+    {{
+        let hs_local_pos = (particle.{position} - sdf_transform.origin) / sdf_transform.scale;
+        let hs_dist = sdf_sample(hs_local_pos);
+        if ({test}) {{
+            particle.{age} = particle.{lifetime};
+        }}
+    }}
+"##,
+            position = Attribute::POSITION.name(),
+            age = Attribute::AGE.name(),
+            lifetime = Attribute::LIFETIME.name(),
+            test = if self.kill_inside {
+                "hs_dist < 0."
+            } else {
+                "hs_dist >= 0."
+            },
+        );
+
+        Ok(())
+    }
+
+    fn boxed_clone(&self) -> BoxedModifier {
+        Box::new(self.clone())
+    }
+}
+
+/// Bake a [`Mesh`] into a single-channel 3D [`Image`] signed-distance
+/// volume usable by [`ConformToSdfModifier`] and [`KillSdfModifier`].
+///
+/// This rasterizes the mesh's triangles CPU-side: for every texel of the
+/// `resolution`^3 volume, the (unsigned) distance to the closest triangle is
+/// computed directly, then the sign is resolved by a parity (ray-casting)
+/// inside/outside test along +X. This is `O(resolution^3 * triangle_count)`
+/// and meant to run once at asset-build/load time, not per frame.
+///
+/// Stored texel values are normalized by the mesh's own bounding-box extent
+/// (its largest axis), matching [`SdfVolume::scale`]'s contract that baked
+/// distances are in local-space units, i.e. as if the volume's extent were
+/// `1`. [`ConformToSdfModifier`]'s generated code multiplies a sampled texel
+/// by `sdf_transform.scale` to recover a real-world distance, so storing
+/// anything other than
+/// extent-normalized values here would make that multiplication double-count
+/// (or omit) the scale factor.
+pub fn bake_mesh_to_sdf(mesh: &Mesh, resolution: u32) -> Option<Image> {
+    let positions = mesh.attribute(Mesh::ATTRIBUTE_POSITION)?.as_float3()?;
+    let indices: Vec<u32> = mesh.indices()?.iter().map(|i| i as u32).collect();
+
+    let mut min = Vec3::splat(f32::MAX);
+    let mut max = Vec3::splat(f32::MIN);
+    for p in positions {
+        let p = Vec3::from(*p);
+        min = min.min(p);
+        max = max.max(p);
+    }
+
+    let res = resolution.max(2);
+    let mut data = vec![0f32; (res * res * res) as usize];
+
+    // Normalize distances by the mesh's largest AABB dimension so stored
+    // texels are in local-space units (extent == 1), matching the contract
+    // `sdf_transform.scale` relies on at sample time.
+    let extent = (max - min).max_element().max(f32::EPSILON);
+
+    let triangles: Vec<[Vec3; 3]> = indices
+        .chunks_exact(3)
+        .map(|tri| {
+            [
+                Vec3::from(positions[tri[0] as usize]),
+                Vec3::from(positions[tri[1] as usize]),
+                Vec3::from(positions[tri[2] as usize]),
+            ]
+        })
+        .collect();
+
+    for z in 0..res {
+        for y in 0..res {
+            for x in 0..res {
+                let t = Vec3::new(x as f32, y as f32, z as f32) / (res - 1).max(1) as f32;
+                let p = min + t * (max - min);
+
+                let mut closest_dist = f32::MAX;
+                let mut inside_votes = 0i32;
+                for tri in &triangles {
+                    closest_dist = closest_dist.min(point_triangle_distance(p, tri));
+                    if ray_crosses_triangle(p, tri) {
+                        inside_votes += 1;
+                    }
+                }
+
+                let sign = if inside_votes % 2 == 1 { -1. } else { 1. };
+                let idx = (z * res * res + y * res + x) as usize;
+                data[idx] = (closest_dist / extent) * sign;
+            }
+        }
+    }
+
+    let bytes: Vec<u8> = data.iter().flat_map(|f| f.to_le_bytes()).collect();
+
+    Some(Image::new(
+        Extent3d {
+            width: res,
+            height: res,
+            depth_or_array_layers: res,
+        },
+        TextureDimension::D3,
+        bytes,
+        TextureFormat::R32Float,
+        default(),
+    ))
+}
+
+fn point_triangle_distance(p: Vec3, tri: &[Vec3; 3]) -> f32 {
+    // Closest point on the triangle to `p`, clamped to its edges/vertices.
+    let (a, b, c) = (tri[0], tri[1], tri[2]);
+    let ab = b - a;
+    let ac = c - a;
+    let ap = p - a;
+
+    let d1 = ab.dot(ap);
+    let d2 = ac.dot(ap);
+    if d1 <= 0. && d2 <= 0. {
+        return p.distance(a);
+    }
+
+    let bp = p - b;
+    let d3 = ab.dot(bp);
+    let d4 = ac.dot(bp);
+    if d3 >= 0. && d4 <= d3 {
+        return p.distance(b);
+    }
+
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= 0. && d1 >= 0. && d3 <= 0. {
+        let v = d1 / (d1 - d3);
+        return p.distance(a + ab * v);
+    }
+
+    let cp = p - c;
+    let d5 = ab.dot(cp);
+    let d6 = ac.dot(cp);
+    if d6 >= 0. && d5 <= d6 {
+        return p.distance(c);
+    }
+
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= 0. && d2 >= 0. && d6 <= 0. {
+        let w = d2 / (d2 - d6);
+        return p.distance(a + ac * w);
+    }
+
+    let va = d3 * d6 - d5 * d4;
+    if va <= 0. && (d4 - d3) >= 0. && (d5 - d6) >= 0. {
+        let w = (d4 - d3) / ((d4 - d3) + (d5 - d6));
+        return p.distance(b + (c - b) * w);
+    }
+
+    let denom = 1. / (va + vb + vc);
+    let v = vb * denom;
+    let w = vc * denom;
+    p.distance(a + ab * v + ac * w)
+}
+
+/// Test whether a ray cast from `p` along `+X` crosses the interior of
+/// `tri`, counted once for the whole triangle.
+///
+/// The earlier approach counted crossings of the ray with each of the
+/// triangle's 3 edges individually: a ray genuinely piercing the triangle's
+/// interior generically crosses exactly 2 of those edges (entering one side,
+/// leaving through another), which is an *even* count — the opposite of what
+/// a parity vote needs, and the bug that made `bake_mesh_to_sdf` classify
+/// virtually every sample as outside. Instead, decide containment once per
+/// triangle: project onto the YZ plane (perpendicular to the ray) and do a
+/// standard 2D point-in-triangle test there, then confirm the corresponding
+/// hit on the triangle's plane is actually ahead of `p` along `+X`.
+fn ray_crosses_triangle(p: Vec3, tri: &[Vec3; 3]) -> bool {
+    let (a, b, c) = (tri[0], tri[1], tri[2]);
+
+    // 2D cross product (in YZ) of (q - v0) and (v1 - v0), i.e. which side of
+    // edge v0->v1 point q falls on.
+    let side = |v0: Vec3, v1: Vec3, q: Vec3| -> f32 {
+        (v1.y - v0.y) * (q.z - v0.z) - (v1.z - v0.z) * (q.y - v0.y)
+    };
+
+    let d1 = side(a, b, p);
+    let d2 = side(b, c, p);
+    let d3 = side(c, a, p);
+
+    let has_neg = d1 < 0. || d2 < 0. || d3 < 0.;
+    let has_pos = d1 > 0. || d2 > 0. || d3 > 0.;
+    if has_neg && has_pos {
+        return false; // p isn't inside the (y, z) projection of the triangle
+    }
+
+    // Solve the triangle's plane equation for the X coordinate at (p.y, p.z).
+    let normal = (b - a).cross(c - a);
+    if normal.x.abs() < 1e-8 {
+        return false; // edge-on to the ray: no well-defined single crossing
+    }
+    let hit_x = a.x - (normal.y * (p.y - a.y) + normal.z * (p.z - a.z)) / normal.x;
+    hit_x > p.x
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unit_triangle_in_yz(at_x: f32) -> [Vec3; 3] {
+        // Triangle spanning (y, z) in [0, 2] x [0, 2], at a fixed x plane.
+        [
+            Vec3::new(at_x, 0., 0.),
+            Vec3::new(at_x, 2., 0.),
+            Vec3::new(at_x, 0., 2.),
+        ]
+    }
+
+    #[test]
+    fn ray_crosses_triangle_through_its_interior() {
+        let tri = unit_triangle_in_yz(1.0);
+        // A point at x=0, (y,z) well inside the triangle's projection.
+        assert!(ray_crosses_triangle(Vec3::new(0., 0.5, 0.5), &tri));
+    }
+
+    #[test]
+    fn ray_does_not_cross_triangle_outside_its_projection() {
+        let tri = unit_triangle_in_yz(1.0);
+        assert!(!ray_crosses_triangle(Vec3::new(0., 5., 5.), &tri));
+    }
+
+    #[test]
+    fn ray_does_not_cross_triangle_behind_the_cast_point() {
+        let tri = unit_triangle_in_yz(1.0);
+        // (y, z) is inside the projection, but the triangle is behind p.
+        assert!(!ray_crosses_triangle(Vec3::new(2., 0.5, 0.5), &tri));
+    }
+
+    #[test]
+    fn a_closed_box_has_an_even_number_of_crossings_from_outside() {
+        // A point clearly outside a box (2 triangles per face) should see
+        // an even number of +X crossings. Only the two faces the ray meets
+        // are listed: the other four are parallel to X and never count. The
+        // ray from (-5, 0, 0) runs along the shared diagonal of each face's
+        // two triangles, so all four triangles count here.
+        let half = 1.0;
+        let box_tris = [
+            // +X face, where a ray travelling along +X from a point at
+            // x = -5 straight through the box exits.
+            [
+                Vec3::new(half, -half, -half),
+                Vec3::new(half, half, -half),
+                Vec3::new(half, half, half),
+            ],
+            [
+                Vec3::new(half, -half, -half),
+                Vec3::new(half, half, half),
+                Vec3::new(half, -half, half),
+            ],
+            // -X face, the matching entry crossing.
+            [
+                Vec3::new(-half, -half, -half),
+                Vec3::new(-half, half, half),
+                Vec3::new(-half, half, -half),
+            ],
+            [
+                Vec3::new(-half, -half, -half),
+                Vec3::new(-half, -half, half),
+                Vec3::new(-half, half, half),
+            ],
+        ];
+
+        let p = Vec3::new(-5., 0., 0.);
+        let crossings = box_tris
+            .iter()
+            .filter(|tri| ray_crosses_triangle(p, tri))
+            .count();
+        assert_eq!(crossings % 2, 0);
+    }
+
+    #[test]
+    fn point_triangle_distance_is_zero_on_the_triangle() {
+        let tri = [Vec3::ZERO, Vec3::X, Vec3::Y];
+        assert!((point_triangle_distance(Vec3::new(0.2, 0.2, 0.), &tri)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn point_triangle_distance_clamps_to_nearest_vertex() {
+        let tri = [Vec3::ZERO, Vec3::X, Vec3::Y];
+        let far = Vec3::new(-10., -10., 0.);
+        assert!((point_triangle_distance(far, &tri) - far.distance(Vec3::ZERO)).abs() < 1e-5);
+    }
+}
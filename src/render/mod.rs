@@ -0,0 +1,3 @@
+pub mod shader_cache;
+
+pub use shader_cache::{ShaderCache, ShaderCachePlugin};
@@ -1,11 +1,35 @@
+use std::{
+    hash::{Hash, Hasher},
+    path::{Path, PathBuf},
+};
+
 use bevy::{
-    asset::{Assets, Handle},
-    ecs::change_detection::ResMut,
-    log::debug,
+    app::{App, Plugin, Startup},
+    asset::{AssetServer, Assets, Handle},
+    ecs::{change_detection::ResMut, system::Resource},
+    log::{debug, trace, warn},
     render::render_resource::Shader,
-    utils::HashMap,
+    tasks::IoTaskPool,
+    utils::{AHasher, HashMap},
 };
 
+/// Content-hash digest of a baked shader's source, used as the
+/// [`ShaderCache`] key instead of the full WGSL string.
+///
+/// Storing the digest instead of the (potentially large) source means the
+/// cache does not keep a copy of each string, and repeat lookups for the
+/// same variant compare a single `u64` instead of the whole string. The
+/// source is still hashed once on every [`ShaderCache::get_or_insert`]
+/// call, since that call takes the source, not a digest.
+pub type ShaderDigest = u64;
+
+/// Compute the [`ShaderDigest`] of a baked shader source string.
+pub fn hash_shader_source(source: &str) -> ShaderDigest {
+    let mut hasher = AHasher::default();
+    source.hash(&mut hasher);
+    hasher.finish()
+}
+
 /// Cache of baked shaders variants.
 ///
 /// Baked shader variants are shaders where the placeholders `{{PLACEHOLDER}}`
@@ -14,13 +38,40 @@ use bevy::{
 /// Shaders present in the cache are allocated [`Shader`] resources. Note that a
 /// [`Shader`] resource _may_ further be preprocessed to replace `#define`
 /// directives; to this extent, some entries may not be compilable WGSL as is.
-#[derive(Default)]
+///
+/// Entries are keyed by a [`ShaderDigest`] of their baked source rather than
+/// the source itself, so that lookups compare digests instead of the full
+/// WGSL string; each lookup still hashes the source once. The cache can
+/// optionally be backed by an on-disk directory (see
+/// [`ShaderCache::with_disk_cache`]), so that variants baked in a previous
+/// run are reloaded as [`Shader`] assets at startup. Callers still bake the
+/// source they pass to [`ShaderCache::get_or_insert`], and pipelines are
+/// still compiled on first use.
+#[derive(Resource, Default)]
 pub struct ShaderCache {
-    /// Map of allocated shader resources from their baked shader code.
-    cache: HashMap<String, Handle<Shader>>,
+    /// Map of allocated shader resources from the content-hash digest of
+    /// their baked shader code.
+    cache: HashMap<ShaderDigest, Handle<Shader>>,
+    /// Optional directory where baked shader sources are persisted, keyed by
+    /// their digest, and reloaded from on the next startup.
+    disk_cache_dir: Option<PathBuf>,
 }
 
 impl ShaderCache {
+    /// Enable on-disk persistence of baked shader variants under `dir`.
+    ///
+    /// Each baked source is saved as `dir/<digest>.wgsl`, where `<digest>`
+    /// is its [`ShaderDigest`] formatted as hex. Subsequent app runs reload
+    /// these files into the cache via [`ShaderCache::load_from_disk`], after
+    /// which [`ShaderCache::get_or_insert`] returns the reloaded handle for a
+    /// matching source instead of allocating a new [`Shader`]. It still
+    /// takes the baked source, so the baking step that produces it still
+    /// runs.
+    pub fn with_disk_cache(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.disk_cache_dir = Some(dir.into());
+        self
+    }
+
     /// Get an existing baked shader variant, or insert it into the cache and
     /// allocate a new [`Shader`] resource for it.
     ///
@@ -30,13 +81,200 @@ impl ShaderCache {
         source: &str,
         shaders: &mut ResMut<Assets<Shader>>,
     ) -> Handle<Shader> {
-        if let Some(handle) = self.cache.get(source) {
-            handle.clone()
-        } else {
-            let handle = shaders.add(Shader::from_wgsl(source.to_string()));
-            debug!("Inserted new configured shader: {:?}\n{}", handle, source);
-            self.cache.insert(source.to_string(), handle.clone());
-            handle
+        let digest = hash_shader_source(source);
+
+        if let Some(handle) = self.cache.get(&digest) {
+            return handle.clone();
+        }
+
+        self.persist_to_disk(digest, source);
+
+        let handle = shaders.add(Shader::from_wgsl(source.to_string()));
+        debug!(
+            "Inserted new configured shader (digest={:016x}): {:?}\n{}",
+            digest, handle, source
+        );
+        self.cache.insert(digest, handle.clone());
+        handle
+    }
+
+    /// Pre-populate the cache from the on-disk directory set via
+    /// [`ShaderCache::with_disk_cache`], if any.
+    ///
+    /// This should be called once at startup, before any effect is spawned,
+    /// so that variants baked during a previous run are immediately
+    /// available from [`ShaderCache::get_or_insert`]. Only the [`Shader`]
+    /// assets are restored; their pipelines are still compiled on first use.
+    pub fn load_from_disk(&mut self, shaders: &mut ResMut<Assets<Shader>>) {
+        let Some(dir) = self.disk_cache_dir.clone() else {
+            return;
+        };
+
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            return;
+        };
+
+        let mut loaded = 0usize;
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("wgsl") {
+                continue;
+            }
+            let Some(digest) = digest_from_cache_path(&path) else {
+                continue;
+            };
+            let Ok(source) = std::fs::read_to_string(&path) else {
+                continue;
+            };
+
+            let handle = shaders.add(Shader::from_wgsl(source));
+            self.cache.insert(digest, handle);
+            loaded += 1;
+        }
+
+        debug!(
+            "Loaded {} baked shader variant(s) from disk cache at {:?}",
+            loaded, dir
+        );
+    }
+
+    /// Insert each already-baked shader source in `sources` into the cache
+    /// (skipping ones already present) and nudge the asset server to start
+    /// processing them now rather than on an effect's first spawn.
+    ///
+    /// This only registers the [`Shader`] assets ahead of time; it does not
+    /// itself create or queue any render pipeline. A [`Shader`] asset is
+    /// turned into a compiled pipeline lazily, the first time a render-world
+    /// system looks one up in the `SpecializedComputePipelines`/
+    /// `SpecializedRenderPipelines` caches, so calling `warm` does not by
+    /// itself eliminate a first-spawn pipeline-compile hitch — it only moves
+    /// the cheaper work (hashing, WGSL string allocation, disk persistence,
+    /// and asset-server bookkeeping) earlier, so that whatever first-spawn
+    /// cost remains is pipeline creation alone.
+    ///
+    /// Callers are expected to bake each of an [`EffectAsset`](crate::EffectAsset)'s
+    /// shader variants into `sources` themselves (that baking step lives with
+    /// the asset definition, not in this cache); this keeps `ShaderCache` usable
+    /// from contexts — tests, tools — that bake or load WGSL from elsewhere.
+    pub fn warm<'a>(
+        &mut self,
+        sources: impl IntoIterator<Item = &'a str>,
+        shaders: &mut ResMut<Assets<Shader>>,
+        asset_server: &AssetServer,
+    ) {
+        for source in sources {
+            let digest = hash_shader_source(source);
+            if self.cache.contains_key(&digest) {
+                continue;
+            }
+
+            let handle = self.get_or_insert(source, shaders);
+            // Ensure the shader asset is actually processed in the
+            // background rather than lazily on first use, by forcing the
+            // asset server to process its dependents now.
+            asset_server.load_asset(handle.clone().untyped());
+            trace!("Warmed shader variant (digest={:016x})", digest);
         }
     }
+
+    fn persist_to_disk(&self, digest: ShaderDigest, source: &str) {
+        let Some(dir) = &self.disk_cache_dir else {
+            return;
+        };
+
+        let dir = dir.clone();
+        let path = cache_path_for_digest(&dir, digest);
+        let source = source.to_string();
+
+        // Writing to disk is fire-and-forget: a failure here just means the
+        // next startup does not reload this one variant from disk, which is
+        // the same as running without a disk cache.
+        IoTaskPool::get()
+            .spawn(async move {
+                if let Err(err) = std::fs::create_dir_all(&dir) {
+                    warn!("Failed to create shader disk cache directory {:?}: {}", dir, err);
+                    return;
+                }
+                if let Err(err) = std::fs::write(&path, source) {
+                    warn!("Failed to persist baked shader to {:?}: {}", path, err);
+                }
+            })
+            .detach();
+    }
+}
+
+/// Installs [`ShaderCache`] as a resource and restores it from its on-disk
+/// cache directory (if any) at startup, via [`ShaderCache::load_from_disk`].
+///
+/// This is the only integration point this crate can provide without
+/// knowing where an effect's shader variants get baked (that step lives
+/// with [`EffectAsset`](crate::EffectAsset), not here): it reloads variants
+/// persisted by a previous run as [`Shader`] assets in a [`Startup`]
+/// system. Their pipelines are still compiled on first use. This plugin
+/// does not call [`ShaderCache::warm`]; that needs a caller that has baked
+/// sources in hand, e.g. a system run after effects finish loading.
+pub struct ShaderCachePlugin {
+    /// Directory to persist and restore baked shader variants from, passed
+    /// to [`ShaderCache::with_disk_cache`]. Leave `None` to run with an
+    /// in-memory-only cache (no restoration across app restarts).
+    pub disk_cache_dir: Option<PathBuf>,
+}
+
+impl Plugin for ShaderCachePlugin {
+    fn build(&self, app: &mut App) {
+        let cache = match &self.disk_cache_dir {
+            Some(dir) => ShaderCache::default().with_disk_cache(dir.clone()),
+            None => ShaderCache::default(),
+        };
+        app.insert_resource(cache)
+            .add_systems(Startup, load_shader_cache_from_disk);
+    }
+}
+
+fn load_shader_cache_from_disk(mut cache: ResMut<ShaderCache>, mut shaders: ResMut<Assets<Shader>>) {
+    cache.load_from_disk(&mut shaders);
+}
+
+fn cache_path_for_digest(dir: &Path, digest: ShaderDigest) -> PathBuf {
+    dir.join(format!("{:016x}.wgsl", digest))
+}
+
+fn digest_from_cache_path(path: &Path) -> Option<ShaderDigest> {
+    let stem = path.file_stem()?.to_str()?;
+    ShaderDigest::from_str_radix(stem, 16).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hash_shader_source_is_deterministic() {
+        let source = "@compute @workgroup_size(64) fn main() {}";
+        assert_eq!(hash_shader_source(source), hash_shader_source(source));
+    }
+
+    #[test]
+    fn hash_shader_source_distinguishes_different_sources() {
+        let a = "fn main() { let x = 1; }";
+        let b = "fn main() { let x = 2; }";
+        assert_ne!(hash_shader_source(a), hash_shader_source(b));
+    }
+
+    #[test]
+    fn cache_path_round_trips_through_digest_from_cache_path() {
+        let dir = Path::new("/tmp/hanabi_shader_cache");
+        let digest: ShaderDigest = 0xdead_beef_1234_5678;
+
+        let path = cache_path_for_digest(dir, digest);
+
+        assert_eq!(path.extension().and_then(|e| e.to_str()), Some("wgsl"));
+        assert_eq!(digest_from_cache_path(&path), Some(digest));
+    }
+
+    #[test]
+    fn digest_from_cache_path_rejects_non_hex_stems() {
+        let path = Path::new("/tmp/hanabi_shader_cache/not_a_digest.wgsl");
+        assert_eq!(digest_from_cache_path(path), None);
+    }
 }